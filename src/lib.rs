@@ -2,13 +2,32 @@ use image::Pixel;
 use image::{DynamicImage, GenericImage, RgbaImage};
 use num_traits::Signed;
 use rayon::prelude::*;
-use std::cmp::{max, min};
+use std::cmp::min;
 use std::default::Default;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 type Rgba = image::Rgba<u8>;
 
 pub fn compare_images<I1, I2>(img1: &I1, img2: &I2, opt: &ComparisonOptions) -> Compare
+where
+    I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+    I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+{
+    match opt.normalization {
+        Normalization::None => compare_images_core(img1, img2, opt),
+        Normalization::Equalize => {
+            let n1 = equalize_brightness(img1);
+            let n2 = equalize_brightness(img2);
+            compare_images_core(&n1, &n2, opt)
+        }
+        Normalization::MatchHistogram => {
+            let n2 = match_brightness_histogram(img2, img1);
+            compare_images_core(img1, &n2, opt)
+        }
+    }
+}
+
+fn compare_images_core<I1, I2>(img1: &I1, img2: &I2, opt: &ComparisonOptions) -> Compare
 where
     I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
     I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
@@ -16,6 +35,7 @@ where
     let (width, height) = width_height_from_2_images(img1, img2);
     let mut img_out = RgbaImage::new(width, height);
     let mismatch_count = AtomicUsize::new(0);
+    let blocked_count = AtomicUsize::new(0);
 
     img_out
         .par_chunks_mut(4)
@@ -24,25 +44,133 @@ where
             let pixel = image::Rgba::from_slice_mut(pixel);
             let (x, y) = xy_from_index(width, index as u32);
             let pixel1 = img1.get_pixel(x, y);
-            let pixel2 = img2.get_pixel(x, y);
-            let are_equals = compare_pixel(&pixel1, &pixel2, img1, img2, (x, y), opt);
 
-            if are_equals {
-                *pixel = pixel1;
-            } else {
-                *pixel = *Rgba::from_slice(&[255, 0, 255, 255]);
-                mismatch_count.fetch_add(1, Ordering::SeqCst);
+            if opt.is_blocked(x, y) {
+                *pixel = *Rgba::from_slice(&[128, 128, 128, 255]);
+                blocked_count.fetch_add(1, Ordering::SeqCst);
+                return;
             }
+
+            let pixel2 = img2.get_pixel(x, y);
+            let diff = compare_pixel(&pixel1, &pixel2, img1, img2, (x, y), (x, y), opt);
+
+            *pixel = match diff {
+                PixelDiff::Same => fade(&base_pixel(&pixel1, &pixel2, opt), opt.blend_factor),
+                PixelDiff::Antialiased => *Rgba::from_slice(&[
+                    opt.antialiased_color.0,
+                    opt.antialiased_color.1,
+                    opt.antialiased_color.2,
+                    255,
+                ]),
+                PixelDiff::Different => {
+                    mismatch_count.fetch_add(1, Ordering::SeqCst);
+                    *Rgba::from_slice(&[opt.diff_color.0, opt.diff_color.1, opt.diff_color.2, 255])
+                }
+            };
         });
 
     let mismatch_count = mismatch_count.load(Ordering::SeqCst) as u32;
+    let blocked_count = blocked_count.load(Ordering::SeqCst) as u32;
 
     Compare {
         image: DynamicImage::ImageRgba8(img_out),
-        mismatch_percent: (mismatch_count * 100) as f64 / (width * height) as f64,
+        mismatch_percent: mismatch_ratio(mismatch_count, width * height, blocked_count),
+        structural_similarity: opt
+            .structural_similarity
+            .then(|| structural_similarity(img1, img2)),
     }
 }
 
+/// Percentage of mismatched pixels among the pixels actually compared, i.e. excluding
+/// any blocked out by `ComparisonOptions::block_out`. Returns `0.0` (nothing mismatched,
+/// because nothing was compared) rather than dividing by zero when `block_out` covers
+/// the entire image.
+fn mismatch_ratio(mismatch_count: u32, total: u32, blocked_count: u32) -> f64 {
+    let compared = total - blocked_count;
+
+    if compared == 0 {
+        return 0.0;
+    }
+
+    (mismatch_count * 100) as f64 / compared as f64
+}
+
+/// Side length (in pixels) of the non-overlapping window SSIM is averaged over.
+const SSIM_WINDOW: u32 = 8;
+
+/// SSIM stabilization constant for luminance, `(0.01 * 255)^2`.
+const SSIM_C1: f64 = 6.5025;
+
+/// SSIM stabilization constant for contrast/structure, `(0.03 * 255)^2`.
+const SSIM_C2: f64 = 58.5225;
+
+/// Compute the structural similarity (SSIM) index between two images, a 0.0-1.0
+/// score where 1.0 means structurally identical. Unlike `mismatch_percent`, SSIM
+/// is sensitive to changes in luminance, contrast and structure rather than raw
+/// per-pixel differences, so it can tell apart "recompressed" from "really different".
+///
+/// The luminance channel of both images is split into `SSIM_WINDOW`-sized windows;
+/// each window's SSIM is computed independently (in parallel) and the results are
+/// averaged into a single global index, following the standard windowed SSIM algorithm.
+pub fn structural_similarity<I1, I2>(img1: &I1, img2: &I2) -> f64
+where
+    I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+    I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+{
+    let (width, height) = width_height_from_2_images(img1, img2);
+
+    let windows: Vec<(u32, u32)> = (0..height)
+        .step_by(SSIM_WINDOW as usize)
+        .flat_map(|y| (0..width).step_by(SSIM_WINDOW as usize).map(move |x| (x, y)))
+        .collect();
+
+    if windows.is_empty() {
+        return 1.0;
+    }
+
+    let total: f64 = windows
+        .par_iter()
+        .map(|&(wx, wy)| {
+            let w = min(SSIM_WINDOW, width - wx);
+            let h = min(SSIM_WINDOW, height - wy);
+            window_ssim(img1, img2, wx, wy, w, h)
+        })
+        .sum();
+
+    total / windows.len() as f64
+}
+
+fn window_ssim<I1, I2>(img1: &I1, img2: &I2, wx: u32, wy: u32, w: u32, h: u32) -> f64
+where
+    I1: GenericImage<Pixel = Rgba>,
+    I2: GenericImage<Pixel = Rgba>,
+{
+    let n = (w * h) as f64;
+    let (mut sum_x, mut sum_y, mut sum_xx, mut sum_yy, mut sum_xy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+
+    for y in wy..wy + h {
+        for x in wx..wx + w {
+            let lx = get_brightness(&img1.get_pixel(x, y)) as f64;
+            let ly = get_brightness(&img2.get_pixel(x, y)) as f64;
+
+            sum_x += lx;
+            sum_y += ly;
+            sum_xx += lx * lx;
+            sum_yy += ly * ly;
+            sum_xy += lx * ly;
+        }
+    }
+
+    let mean_x = sum_x / n;
+    let mean_y = sum_y / n;
+    let var_x = sum_xx / n - mean_x * mean_x;
+    let var_y = sum_yy / n - mean_y * mean_y;
+    let covar_xy = sum_xy / n - mean_x * mean_y;
+
+    ((2.0 * mean_x * mean_y + SSIM_C1) * (2.0 * covar_xy + SSIM_C2))
+        / ((mean_x * mean_x + mean_y * mean_y + SSIM_C1) * (var_x + var_y + SSIM_C2))
+}
+
 /// Compare 2 images and return the mismatch percentage based on the number of pixels that are different.`.
 ///
 /// # Examples
@@ -68,52 +196,463 @@ where
 /// }
 /// ```
 pub fn get_mismatch_percent<I1, I2>(img1: &I1, img2: &I2, opt: &ComparisonOptions) -> f64
+where
+    I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+    I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+{
+    match opt.normalization {
+        Normalization::None => get_mismatch_percent_core(img1, img2, opt),
+        Normalization::Equalize => {
+            let n1 = equalize_brightness(img1);
+            let n2 = equalize_brightness(img2);
+            get_mismatch_percent_core(&n1, &n2, opt)
+        }
+        Normalization::MatchHistogram => {
+            let n2 = match_brightness_histogram(img2, img1);
+            get_mismatch_percent_core(img1, &n2, opt)
+        }
+    }
+}
+
+fn get_mismatch_percent_core<I1, I2>(img1: &I1, img2: &I2, opt: &ComparisonOptions) -> f64
 where
     I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
     I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
 {
     let (width, height) = width_height_from_2_images(img1, img2);
 
-    let mismatch_count: u64 = (0..width * height)
+    let (mismatch_count, blocked_count) = (0..width * height)
         .into_par_iter()
         .map(|index| {
             let (x, y) = xy_from_index(width, index);
+
+            if opt.is_blocked(x, y) {
+                return (0u64, 1u64);
+            }
+
             let pixel1 = img1.get_pixel(x, y);
             let pixel2 = img2.get_pixel(x, y);
-            let are_equals = compare_pixel(&pixel1, &pixel2, img1, img2, (x, y), opt);
+            let diff = compare_pixel(&pixel1, &pixel2, img1, img2, (x, y), (x, y), opt);
 
-            if are_equals {
-                0u64
+            (if diff == PixelDiff::Different { 1u64 } else { 0u64 }, 0u64)
+        })
+        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    mismatch_ratio(mismatch_count as u32, width * height, blocked_count as u32)
+}
+
+/// Find the first offset `(x, y)` (in raster order: top-to-bottom, left-to-right)
+/// in `haystack` where `needle` occurs, accepting a match when the fraction of
+/// mismatching pixels (per `compare_pixel`'s tolerance) does not exceed
+/// `max_mismatch_ratio`. Offsets are searched in parallel with rayon, and each
+/// candidate window short-circuits as soon as its mismatch budget is blown.
+pub fn find_subimage<I1, I2>(
+    haystack: &I1,
+    needle: &I2,
+    max_mismatch_ratio: f64,
+    opt: &ComparisonOptions,
+) -> Option<(u32, u32)>
+where
+    I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+    I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+{
+    candidate_offsets(haystack, needle)?
+        .into_par_iter()
+        .find_map_first(|offset| {
+            if subimage_matches(haystack, needle, offset, max_mismatch_ratio, opt) {
+                Some(offset)
             } else {
-                1u64
+                None
             }
         })
-        .sum();
+}
+
+/// Find every offset `(x, y)` in `haystack` where `needle` occurs. See `find_subimage`.
+pub fn find_all_subimages<I1, I2>(
+    haystack: &I1,
+    needle: &I2,
+    max_mismatch_ratio: f64,
+    opt: &ComparisonOptions,
+) -> Vec<(u32, u32)>
+where
+    I1: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+    I2: GenericImage<Pixel = Rgba> + 'static + std::marker::Sync,
+{
+    match candidate_offsets(haystack, needle) {
+        Some(offsets) => offsets
+            .into_par_iter()
+            .filter(|&offset| subimage_matches(haystack, needle, offset, max_mismatch_ratio, opt))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn candidate_offsets<I1, I2>(haystack: &I1, needle: &I2) -> Option<Vec<(u32, u32)>>
+where
+    I1: GenericImage<Pixel = Rgba>,
+    I2: GenericImage<Pixel = Rgba>,
+{
+    let (hw, hh) = haystack.dimensions();
+    let (nw, nh) = needle.dimensions();
+
+    if nw > hw || nh > hh {
+        return None;
+    }
+
+    Some(
+        (0..=hh - nh)
+            .flat_map(|y| (0..=hw - nw).map(move |x| (x, y)))
+            .collect(),
+    )
+}
+
+fn subimage_matches<I1, I2>(
+    haystack: &I1,
+    needle: &I2,
+    offset: (u32, u32),
+    max_mismatch_ratio: f64,
+    opt: &ComparisonOptions,
+) -> bool
+where
+    I1: GenericImage<Pixel = Rgba>,
+    I2: GenericImage<Pixel = Rgba>,
+{
+    let (nw, nh) = needle.dimensions();
+    let max_mismatches = (max_mismatch_ratio * (nw * nh) as f64) as u64;
+    let mut mismatches = 0u64;
 
-    let mismatch_percent = (mismatch_count * 100) as f64 / (width * height) as f64;
-    mismatch_percent
+    for ny in 0..nh {
+        for nx in 0..nw {
+            let (hx, hy) = (offset.0 + nx, offset.1 + ny);
+            let pixel1 = haystack.get_pixel(hx, hy);
+            let pixel2 = needle.get_pixel(nx, ny);
+            let diff = compare_pixel(&pixel1, &pixel2, haystack, needle, (hx, hy), (nx, ny), opt);
+
+            if diff == PixelDiff::Different {
+                mismatches += 1;
+
+                if mismatches > max_mismatches {
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Build a 256-bin histogram of the luminance channel (see `get_brightness`).
+fn luminance_histogram<I>(image: &I) -> [u32; 256]
+where
+    I: GenericImage<Pixel = Rgba>,
+{
+    let mut histogram = [0u32; 256];
+    let (width, height) = image.dimensions();
+
+    for y in 0..height {
+        for x in 0..width {
+            let bin = get_brightness(&image.get_pixel(x, y)).round().clamp(0.0, 255.0) as usize;
+            histogram[bin] += 1;
+        }
+    }
+
+    histogram
+}
+
+fn cumulative_histogram(histogram: &[u32; 256]) -> [u32; 256] {
+    let mut cdf = [0u32; 256];
+    let mut running = 0u32;
+
+    for (i, &count) in histogram.iter().enumerate() {
+        running += count;
+        cdf[i] = running;
+    }
+
+    cdf
+}
+
+/// Build a luminance remapping table that spreads `cdf`'s distribution evenly
+/// across the full 0-255 brightness range (classic histogram equalization).
+fn equalization_table(cdf: &[u32; 256]) -> [u8; 256] {
+    let total = cdf[255];
+    let cdf_min = cdf.iter().copied().find(|&v| v > 0).unwrap_or(0);
+    let mut table = [0u8; 256];
+
+    if total <= cdf_min {
+        for (i, slot) in table.iter_mut().enumerate() {
+            *slot = i as u8;
+        }
+        return table;
+    }
+
+    for (i, slot) in table.iter_mut().enumerate() {
+        let value = (cdf[i] - cdf_min) as f64 * 255.0 / (total - cdf_min) as f64;
+        *slot = value.round().clamp(0.0, 255.0) as u8;
+    }
+
+    table
+}
+
+/// Build a table mapping `source_cdf`'s luminance levels onto the closest
+/// matching level of `reference_cdf` (classic histogram-matching lookup).
+fn histogram_matching_table(source_cdf: &[u32; 256], reference_cdf: &[u32; 256]) -> [u8; 256] {
+    let mut table = [0u8; 256];
+
+    for (v, slot) in table.iter_mut().enumerate() {
+        let target = source_cdf[v];
+        let mut best = 0usize;
+        let mut best_diff = u32::MAX;
+
+        for (j, &reference) in reference_cdf.iter().enumerate() {
+            let diff = abs_sub(reference as i64, target as i64) as u32;
+
+            if diff < best_diff {
+                best_diff = diff;
+                best = j;
+            }
+        }
+
+        *slot = best as u8;
+    }
+
+    table
+}
+
+/// Apply a 0-255 -> 0-255 luminance `table` to every pixel of `image`, scaling
+/// each channel uniformly so hue is preserved.
+fn remap_luminance<I>(image: &I, table: &[u8; 256]) -> RgbaImage
+where
+    I: GenericImage<Pixel = Rgba>,
+{
+    let (width, height) = image.dimensions();
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = image.get_pixel(x, y);
+        let old_luminance = get_brightness(&pixel);
+        let bin = old_luminance.round().clamp(0.0, 255.0) as usize;
+        let new_luminance = table[bin] as f32;
+        let scale = if old_luminance > 0.0 {
+            new_luminance / old_luminance
+        } else {
+            1.0
+        };
+
+        *Rgba::from_slice(&[
+            (pixel.r() as f32 * scale).round().clamp(0.0, 255.0) as u8,
+            (pixel.g() as f32 * scale).round().clamp(0.0, 255.0) as u8,
+            (pixel.b() as f32 * scale).round().clamp(0.0, 255.0) as u8,
+            pixel.a(),
+        ])
+    })
+}
+
+/// Equalize `image`'s luminance histogram, cancelling global brightness/contrast
+/// offsets while preserving hue.
+fn equalize_brightness<I>(image: &I) -> RgbaImage
+where
+    I: GenericImage<Pixel = Rgba>,
+{
+    let cdf = cumulative_histogram(&luminance_histogram(image));
+    remap_luminance(image, &equalization_table(&cdf))
+}
+
+/// Remap `source`'s luminance histogram onto `reference`'s cumulative distribution.
+fn match_brightness_histogram<I1, I2>(source: &I2, reference: &I1) -> RgbaImage
+where
+    I1: GenericImage<Pixel = Rgba>,
+    I2: GenericImage<Pixel = Rgba>,
+{
+    let source_cdf = cumulative_histogram(&luminance_histogram(source));
+    let reference_cdf = cumulative_histogram(&luminance_histogram(reference));
+    let table = histogram_matching_table(&source_cdf, &reference_cdf);
+    remap_luminance(source, &table)
 }
 
 pub struct Compare {
     pub image: DynamicImage,
     pub mismatch_percent: f64,
+    /// SSIM score, present only when `ComparisonOptions::with_structural_similarity`
+    /// was set — computing it is a second full pass over both images, so it stays
+    /// opt-in rather than being charged to every caller.
+    pub structural_similarity: Option<f64>,
 }
 
 pub struct ComparisonOptions {
+    antialias: AntialiasOptions,
+    antialiased_color: (u8, u8, u8),
+    background: (u8, u8, u8),
+    blend_factor: f32,
+    block_out: Vec<(u32, u32, u32, u32)>,
+    diff_base: DiffBase,
+    diff_color: (u8, u8, u8),
     ignore_antialiasing: bool,
     ignore_colors: bool,
+    normalization: Normalization,
+    structural_similarity: bool,
     tolerance: Tolerance,
+    yiq_threshold: Option<f32>,
+}
+
+/// Brightness/contrast normalization applied before comparison (to both images
+/// for `Equalize`, to img2 only for `MatchHistogram`), to cancel out gamma or
+/// exposure differences between two otherwise-identical screenshots. See `ComparisonOptions::normalize_brightness` and `::match_histogram`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Normalization {
+    None,
+    Equalize,
+    MatchHistogram,
+}
+
+/// Tuning knobs for the sibling-extrema anti-aliasing heuristic used when
+/// `ignore_antialiasing` is enabled on `ComparisonOptions`.
+pub struct AntialiasOptions {
+    max_depth: u32,
+    sibling_threshold: u8,
+}
+
+impl AntialiasOptions {
+    pub fn new() -> AntialiasOptions {
+        AntialiasOptions {
+            max_depth: 1,
+            sibling_threshold: 3,
+        }
+    }
+
+    /// How many extra levels of "does this neighbour itself have many siblings"
+    /// to check before giving up. Higher values give each extreme neighbour more
+    /// chances to qualify, so more pixels are confirmed as anti-aliased (and the
+    /// check is slower).
+    pub fn max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Minimum number of brightness-identical neighbours a pixel needs to be
+    /// considered as having "many siblings" during the recursive check.
+    pub fn sibling_threshold(mut self, sibling_threshold: u8) -> Self {
+        self.sibling_threshold = sibling_threshold;
+        self
+    }
+}
+
+impl Default for AntialiasOptions {
+    fn default() -> Self {
+        AntialiasOptions::new()
+    }
 }
 
 impl ComparisonOptions {
     pub fn new() -> ComparisonOptions {
         ComparisonOptions {
+            antialias: Default::default(),
+            antialiased_color: (255, 255, 0),
+            background: (255, 255, 255),
+            blend_factor: 0.0,
+            block_out: Vec::new(),
+            diff_base: DiffBase::Original,
+            diff_color: (255, 0, 0),
             ignore_antialiasing: false,
             ignore_colors: false,
+            normalization: Normalization::None,
+            structural_similarity: false,
             tolerance: Default::default(),
+            yiq_threshold: None,
         }
     }
 
+    /// Also compute `Compare::structural_similarity` (SSIM) in `compare_images`.
+    /// Off by default, since it requires a second full pass over both images;
+    /// call `structural_similarity` directly instead if that's all you need.
+    pub fn with_structural_similarity(mut self) -> Self {
+        self.structural_similarity = true;
+        self
+    }
+
+    /// Equalize each image's luminance histogram independently before comparing,
+    /// cancelling out global brightness/contrast offsets (e.g. from gamma or
+    /// exposure differences) while preserving hue.
+    pub fn normalize_brightness(mut self) -> Self {
+        self.normalization = Normalization::Equalize;
+        self
+    }
+
+    /// Remap img2's luminance histogram onto img1's cumulative distribution before
+    /// comparing, so a differently tone-mapped screenshot lines up with its reference.
+    pub fn match_histogram(mut self) -> Self {
+        self.normalization = Normalization::MatchHistogram;
+        self
+    }
+
+    /// Dim unchanged pixels towards white by `factor` (0.0 leaves them untouched,
+    /// 1.0 turns them fully white) so genuine differences stand out in the diff image.
+    pub fn blend_factor(mut self, factor: f32) -> Self {
+        self.blend_factor = factor;
+        self
+    }
+
+    /// Color (r, g, b) used in the diff image for pixels that are genuinely
+    /// different. Defaults to red.
+    pub fn diff_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.diff_color = (r, g, b);
+        self
+    }
+
+    /// Color (r, g, b) used in the diff image for pixels that differ but were
+    /// attributed to anti-aliasing. Defaults to yellow.
+    pub fn antialiased_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.antialiased_color = (r, g, b);
+        self
+    }
+
+    /// Choose what unchanged pixels are rendered from in the diff image produced
+    /// by `compare_images`. Defaults to `DiffBase::Original`.
+    pub fn diff_base(mut self, diff_base: DiffBase) -> Self {
+        self.diff_base = diff_base;
+        self
+    }
+
+    /// Tune the sibling-extrema anti-aliasing heuristic used when
+    /// `ignore_antialiasing` is set. See `AntialiasOptions`.
+    pub fn antialias_options(mut self, antialias: AntialiasOptions) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Exclude rectangular regions `(x, y, w, h)` from the comparison, for content that
+    /// is expected to vary between screenshots (timestamps, ads, carousels, ...).
+    /// Blocked pixels are skipped from the mismatch count and its denominator, and are
+    /// painted gray in the diff image produced by `compare_images` instead of the diff color.
+    pub fn block_out(mut self, rects: Vec<(u32, u32, u32, u32)>) -> Self {
+        self.block_out = rects;
+        self
+    }
+
+    fn is_blocked(&self, x: u32, y: u32) -> bool {
+        self.block_out
+            .iter()
+            .any(|&(bx, by, bw, bh)| x >= bx && x < bx + bw && y >= by && y < by + bh)
+    }
+
+    /// Set the background color (r, g, b) semi-transparent pixels are blended against
+    /// before comparison by the YIQ perceptual tolerance (see [`yiq_tolerance`](Self::yiq_tolerance)).
+    /// Also the fill color of unchanged pixels when the diff base is `DiffBase::Background`.
+    pub fn background_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.background = (r, g, b);
+        self
+    }
+
+    /// Compare pixels using a perceptual distance in YIQ color space instead of
+    /// per-channel RGB tolerance. `threshold` ranges from 0.0 (identical) to 1.0
+    /// (anything goes); resemble.js uses 0.1 as a reasonable starting point.
+    ///
+    /// This takes precedence over `ignore_colors` and `ignore_antialiasing`: once
+    /// set, every pixel pair that passes the alpha tolerance check is decided by the
+    /// YIQ distance alone, so those two options become no-ops if combined with this one.
+    pub fn yiq_tolerance(mut self, threshold: f32) -> Self {
+        self.yiq_threshold = Some(threshold);
+        self
+    }
+
     pub fn ignore_nothing(mut self) -> Self {
         self.ignore_antialiasing = false;
         self.tolerance.alpha = 0;
@@ -174,14 +713,27 @@ struct Tolerance {
     blue: u8,
 }
 
+/// Outcome of comparing a single pair of pixels.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum PixelDiff {
+    /// Pixels are equal (within tolerance).
+    Same,
+    /// Pixels differ, but the difference was attributed to anti-aliasing and is
+    /// therefore not counted as a mismatch, although it is still highlighted.
+    Antialiased,
+    /// Pixels genuinely differ and count as a mismatch.
+    Different,
+}
+
 fn compare_pixel<I1, I2>(
     pixel1: &Rgba,
     pixel2: &Rgba,
     img1: &I1,
     img2: &I2,
-    position: (u32, u32),
+    position1: (u32, u32),
+    position2: (u32, u32),
     opt: &ComparisonOptions,
-) -> bool
+) -> PixelDiff
 where
     I1: GenericImage<Pixel = Rgba>,
     I2: GenericImage<Pixel = Rgba>,
@@ -191,21 +743,81 @@ where
         pixel2.a() as i16,
         opt.tolerance.alpha as i16,
     ) {
-        false
+        PixelDiff::Different
+    } else if let Some(threshold) = opt.yiq_threshold {
+        bool_to_diff(is_yiq_similar(pixel1, pixel2, threshold, opt.background))
     } else if opt.ignore_colors {
-        is_pixel_brightness_similar(pixel1, pixel2, &opt.tolerance)
+        bool_to_diff(is_pixel_brightness_similar(pixel1, pixel2, &opt.tolerance))
     } else if is_rgb_similar(pixel1, pixel2, &opt.tolerance) {
-        true
+        PixelDiff::Same
     } else if opt.ignore_antialiasing
-        && (is_antialiased(pixel1, img1, &position, &opt.tolerance)
-            || is_antialiased(pixel2, img2, &position, &opt.tolerance))
+        && (is_antialiased(pixel1, img1, &position1, &opt.antialias)
+            || is_antialiased(pixel2, img2, &position2, &opt.antialias))
     {
-        is_pixel_brightness_similar(pixel1, pixel2, &opt.tolerance)
+        if is_pixel_brightness_similar(pixel1, pixel2, &opt.tolerance) {
+            PixelDiff::Antialiased
+        } else {
+            PixelDiff::Different
+        }
     } else {
-        false
+        PixelDiff::Different
     }
 }
 
+fn bool_to_diff(are_equal: bool) -> PixelDiff {
+    if are_equal {
+        PixelDiff::Same
+    } else {
+        PixelDiff::Different
+    }
+}
+
+/// What an unchanged pixel is rendered from in the diff image produced by
+/// `compare_images`.
+pub enum DiffBase {
+    /// Use `img1`'s pixel as-is.
+    Original,
+    /// Use a flat fill of `ComparisonOptions::background_color`.
+    Background,
+    /// Use a 50%/50% blend of `img1` and `img2`.
+    Blend,
+}
+
+fn base_pixel(pixel1: &Rgba, pixel2: &Rgba, opt: &ComparisonOptions) -> Rgba {
+    match opt.diff_base {
+        DiffBase::Original => *pixel1,
+        DiffBase::Background => *Rgba::from_slice(&[
+            opt.background.0,
+            opt.background.1,
+            opt.background.2,
+            255,
+        ]),
+        DiffBase::Blend => *Rgba::from_slice(&[
+            ((pixel1.r() as u16 + pixel2.r() as u16) / 2) as u8,
+            ((pixel1.g() as u16 + pixel2.g() as u16) / 2) as u8,
+            ((pixel1.b() as u16 + pixel2.b() as u16) / 2) as u8,
+            255,
+        ]),
+    }
+}
+
+/// Fade a pixel towards white by `blend_factor` (0.0 keeps it as-is, 1.0 turns it
+/// fully white), so that genuine differences stand out against a dimmed backdrop.
+fn fade(pixel: &Rgba, blend_factor: f32) -> Rgba {
+    if blend_factor <= 0.0 {
+        return *pixel;
+    }
+
+    let channel = |c: u8| (c as f32 + (255.0 - c as f32) * blend_factor) as u8;
+
+    *Rgba::from_slice(&[
+        channel(pixel.r()),
+        channel(pixel.g()),
+        channel(pixel.b()),
+        pixel.a(),
+    ])
+}
+
 fn abs_sub<T>(x: T, y: T) -> T
 where
     T: PartialOrd + Signed,
@@ -221,78 +833,147 @@ fn get_brightness(rgba: &Rgba) -> f32 {
     0.3 * rgba.r() as f32 + 0.59 * rgba.g() as f32 + 0.11 * rgba.b() as f32
 }
 
-fn get_hue(rgba: &Rgba) -> f32 {
-    let (r, g, b) = (rgba.r() as f32, rgba.g() as f32, rgba.b() as f32);
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
+/// Maximum possible squared YIQ delta between two colors, used to normalize
+/// `yiq_delta` into a 0.0-1.0 range.
+const MAX_YIQ_DELTA: f32 = 35215.0;
 
-    if max == min {
-        0.0 // achromatic
-    } else {
-        let d = max - min;
+fn blend_against_background(rgba: &Rgba, background: (u8, u8, u8)) -> (f32, f32, f32) {
+    let alpha = rgba.a() as f32 / 255.0;
+    let r = rgba.r() as f32 * alpha + background.0 as f32 * (1.0 - alpha);
+    let g = rgba.g() as f32 * alpha + background.1 as f32 * (1.0 - alpha);
+    let b = rgba.b() as f32 * alpha + background.2 as f32 * (1.0 - alpha);
+    (r, g, b)
+}
 
-        let h = if max == r {
-            (g - b) / d + (if g < b { 6.0 } else { 0.0 })
-        } else if max == g {
-            (b - r) / d + 2.0
-        } else {
-            (r - g) / d + 4.0
-        };
+fn to_yiq(rgba: &Rgba, background: (u8, u8, u8)) -> (f32, f32, f32) {
+    let (r, g, b) = blend_against_background(rgba, background);
+    let y = 0.298_895_3 * r + 0.586_622_5 * g + 0.114_482_23 * b;
+    let i = 0.595_977_99 * r - 0.274_176_1 * g - 0.321_801_9 * b;
+    let q = 0.211_470_17 * r - 0.522_617_1 * g + 0.311_146_94 * b;
+    (y, i, q)
+}
 
-        h / 6.0
-    }
+fn yiq_delta(p1: &Rgba, p2: &Rgba, background: (u8, u8, u8)) -> f32 {
+    let (y1, i1, q1) = to_yiq(p1, background);
+    let (y2, i2, q2) = to_yiq(p2, background);
+    0.5053 * (y1 - y2).powi(2) + 0.299 * (i1 - i2).powi(2) + 0.1957 * (q1 - q2).powi(2)
+}
+
+fn is_yiq_similar(p1: &Rgba, p2: &Rgba, threshold: f32, background: (u8, u8, u8)) -> bool {
+    yiq_delta(p1, p2, background) <= threshold * MAX_YIQ_DELTA
 }
 
-fn is_antialiased<I>(p1: &Rgba, image: &I, p: &(u32, u32), tolerance: &Tolerance) -> bool
+/// Brightness extremes found among the (up to 8) neighbours of a pixel.
+struct SiblingExtremes {
+    /// Count of neighbours whose brightness exactly matches the center pixel's.
+    identical_count: u8,
+    min_brightness_pos: (u32, u32),
+    max_brightness_pos: (u32, u32),
+}
+
+/// Scan the up-to-8 neighbours of `(x, y)` (clamped at the image edges), counting
+/// how many are brightness-identical to `center` (regardless of hue, so colored
+/// anti-aliased edges are caught too) and tracking which neighbour is darkest
+/// and which is brightest.
+fn neighbour_extremes<I>(image: &I, center: &Rgba, x: u32, y: u32) -> SiblingExtremes
 where
-    I: GenericImage<Pixel = image::Rgba<u8>>,
+    I: GenericImage<Pixel = Rgba>,
 {
-    const DISTANCE: u32 = 1;
-
     let (width, height) = image.dimensions();
-    let (x, y) = (p.0, p.1);
+    let left = if x == 0 { 0 } else { x - 1 };
+    let right = min(x + 2, width);
+    let top = if y == 0 { 0 } else { y - 1 };
+    let bottom = min(y + 2, height);
 
-    let left = max(x - DISTANCE, 0);
-    let right = min(x + DISTANCE + 1, width);
-    let top = max(y - DISTANCE, 0);
-    let bottom = min(y + DISTANCE + 1, height);
+    let center_brightness = get_brightness(center);
+    let mut identical_count = 0u8;
+    let mut min_brightness = f32::INFINITY;
+    let mut min_brightness_pos = (x, y);
+    let mut max_brightness = f32::NEG_INFINITY;
+    let mut max_brightness_pos = (x, y);
 
-    let brightness1 = get_brightness(p1);
-    let hue1 = get_hue(p1);
-    let mut has_equivalent_sibling = 0;
-    let mut has_sibling_with_different_hue = 0;
-    let mut has_high_contrast_sibling = 0;
-
-    for x in left..right {
-        for y in top..bottom {
-            // ignore source pixel
-            if x == p.0 && y == p.1 {
+    for ny in top..bottom {
+        for nx in left..right {
+            if nx == x && ny == y {
                 continue;
             }
 
-            let p2 = image.get_pixel(x, y);
-            let brightness2 = get_brightness(&p2);
-            let hue2 = get_hue(&p2);
+            let neighbour = image.get_pixel(nx, ny);
+            let brightness = get_brightness(&neighbour);
 
-            if abs_sub(brightness1, brightness2) > tolerance.max_brightness {
-                has_high_contrast_sibling += 1;
+            if brightness == center_brightness {
+                identical_count += 1;
             }
 
-            if abs_sub(hue1, hue2) > 0.3 {
-                has_sibling_with_different_hue += 1;
+            if brightness < min_brightness {
+                min_brightness = brightness;
+                min_brightness_pos = (nx, ny);
             }
 
-            if is_rgb_same(&p1, &p2) {
-                has_equivalent_sibling += 1;
-            }
-
-            if has_sibling_with_different_hue > 1 || has_high_contrast_sibling > 1 {
-                return true;
+            if brightness > max_brightness {
+                max_brightness = brightness;
+                max_brightness_pos = (nx, ny);
             }
         }
     }
 
-    has_equivalent_sibling < 2
+    SiblingExtremes {
+        identical_count,
+        min_brightness_pos,
+        max_brightness_pos,
+    }
+}
+
+fn is_on_border(width: u32, height: u32, pos: (u32, u32)) -> bool {
+    pos.0 == 0 || pos.1 == 0 || pos.0 == width - 1 || pos.1 == height - 1
+}
+
+/// Whether the pixel at `pos` itself has many brightness-identical siblings,
+/// checking up to `depth` extra levels into its own darkest/brightest neighbour
+/// before giving up.
+fn has_many_siblings<I>(image: &I, pos: (u32, u32), depth: u32, opt: &AntialiasOptions) -> bool
+where
+    I: GenericImage<Pixel = Rgba>,
+{
+    let pixel = image.get_pixel(pos.0, pos.1);
+    let extremes = neighbour_extremes(image, &pixel, pos.0, pos.1);
+
+    if extremes.identical_count >= opt.sibling_threshold {
+        return true;
+    }
+
+    if depth == 0 {
+        return false;
+    }
+
+    has_many_siblings(image, extremes.min_brightness_pos, depth - 1, opt)
+        || has_many_siblings(image, extremes.max_brightness_pos, depth - 1, opt)
+}
+
+/// Detect whether `p1` sits on an anti-aliased edge rather than a genuine content
+/// change. A pixel is anti-aliased when it has 1 or 2 brightness-identical neighbours and
+/// both its darkest and brightest neighbours are themselves deep inside a region of
+/// many identical siblings (i.e. not on a real edge), and neither extreme neighbour
+/// lies on the image border.
+fn is_antialiased<I>(p1: &Rgba, image: &I, p: &(u32, u32), opt: &AntialiasOptions) -> bool
+where
+    I: GenericImage<Pixel = image::Rgba<u8>>,
+{
+    let (width, height) = image.dimensions();
+    let extremes = neighbour_extremes(image, p1, p.0, p.1);
+
+    if !(1..=2).contains(&extremes.identical_count) {
+        return false;
+    }
+
+    if is_on_border(width, height, extremes.min_brightness_pos)
+        || is_on_border(width, height, extremes.max_brightness_pos)
+    {
+        return false;
+    }
+
+    has_many_siblings(image, extremes.min_brightness_pos, opt.max_depth, opt)
+        && has_many_siblings(image, extremes.max_brightness_pos, opt.max_depth, opt)
 }
 
 fn is_pixel_brightness_similar(p1: &Rgba, p2: &Rgba, tolerance: &Tolerance) -> bool {
@@ -305,10 +986,6 @@ fn is_pixel_brightness_similar(p1: &Rgba, p2: &Rgba, tolerance: &Tolerance) -> b
     )
 }
 
-fn is_rgb_same(p1: &Rgba, p2: &Rgba) -> bool {
-    p1.r() == p2.r() && p1.g() == p2.g() && p1.b() == p2.b()
-}
-
 fn is_similar<T: Signed + std::cmp::PartialOrd>(v1: T, v2: T, tolerance: T) -> bool {
     abs_sub(v1, v2) <= tolerance
 }
@@ -357,6 +1034,242 @@ mod tests {
 
         assert_eq!(r.mismatch_percent, 97.1228);
     }
+
+    #[test]
+    fn yiq_tolerance_ignores_small_color_shift() {
+        let p1 = *Rgba::from_slice(&[200, 100, 50, 255]);
+        let p2 = *Rgba::from_slice(&[205, 102, 48, 255]);
+        assert!(is_yiq_similar(&p1, &p2, 0.1, (255, 255, 255)));
+
+        let p3 = *Rgba::from_slice(&[0, 255, 255, 255]);
+        assert!(!is_yiq_similar(&p1, &p3, 0.1, (255, 255, 255)));
+    }
+
+    #[test]
+    fn yiq_tolerance_takes_precedence_over_ignore_colors() {
+        // red and green have nearly identical brightness but very different hue.
+        let pixel1 = *Rgba::from_slice(&[255, 0, 0, 255]);
+        let pixel2 = *Rgba::from_slice(&[0, 129, 0, 255]);
+        let img = RgbaImage::from_pixel(1, 1, pixel1);
+
+        // `ignore_colors` alone would treat these brightness-similar pixels as
+        // the same; combined with `yiq_tolerance` it has no effect, and the
+        // pixels are correctly flagged as different by the YIQ distance check.
+        let opts = ComparisonOptions::new().ignore_colors().yiq_tolerance(0.1);
+        assert_eq!(
+            compare_pixel(&pixel1, &pixel2, &img, &img, (0, 0), (0, 0), &opts),
+            PixelDiff::Different
+        );
+    }
+
+    #[test]
+    fn structural_similarity_is_1_for_identical_images() {
+        let img = RgbaImage::from_fn(16, 16, |x, y| {
+            *Rgba::from_slice(&[(x * 16) as u8, (y * 16) as u8, 128, 255])
+        });
+
+        assert_eq!(structural_similarity(&img, &img), 1.0);
+    }
+
+    #[test]
+    fn structural_similarity_drops_for_different_images() {
+        let img1 = RgbaImage::from_pixel(16, 16, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let img2 = RgbaImage::from_pixel(16, 16, *Rgba::from_slice(&[255, 255, 255, 255]));
+
+        assert!(structural_similarity(&img1, &img2) < 0.5);
+    }
+
+    #[test]
+    fn compare_images_only_computes_ssim_when_requested() {
+        let img1 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let img2 = img1.clone();
+
+        let opts = &ComparisonOptions::new().ignore_nothing();
+        assert_eq!(compare_images(&img1, &img2, opts).structural_similarity, None);
+
+        let opts = &ComparisonOptions::new()
+            .ignore_nothing()
+            .with_structural_similarity();
+        assert_eq!(
+            compare_images(&img1, &img2, opts).structural_similarity,
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn block_out_excludes_region_from_mismatch_percent() {
+        let img1 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let mut img2 = img1.clone();
+
+        // only the top-left 2x2 block differs
+        for y in 0..2 {
+            for x in 0..2 {
+                img2.put_pixel(x, y, *Rgba::from_slice(&[255, 255, 255, 255]));
+            }
+        }
+
+        let opts = &ComparisonOptions::new().ignore_nothing();
+        assert_eq!(get_mismatch_percent(&img1, &img2, opts), 25.0);
+
+        let opts = &ComparisonOptions::new()
+            .ignore_nothing()
+            .block_out(vec![(0, 0, 2, 2)]);
+
+        assert_eq!(get_mismatch_percent(&img1, &img2, opts), 0.0);
+    }
+
+    #[test]
+    fn block_out_covering_whole_image_is_zero_not_nan() {
+        let img1 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let img2 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[255, 255, 255, 255]));
+
+        let opts = &ComparisonOptions::new()
+            .ignore_nothing()
+            .block_out(vec![(0, 0, 4, 4)]);
+
+        assert_eq!(get_mismatch_percent(&img1, &img2, opts), 0.0);
+        assert_eq!(compare_images(&img1, &img2, opts).mismatch_percent, 0.0);
+    }
+
+    #[test]
+    fn antialiased_transition_pixel_is_detected() {
+        // solid black | mid-gray transition column | solid white
+        let img = RgbaImage::from_fn(5, 5, |x, _y| {
+            let v = if x <= 1 {
+                0
+            } else if x == 2 {
+                128
+            } else {
+                255
+            };
+            *Rgba::from_slice(&[v, v, v, 255])
+        });
+
+        let antialias = AntialiasOptions::new();
+        let center = img.get_pixel(2, 2);
+        assert!(is_antialiased(center, &img, &(2, 2), &antialias));
+    }
+
+    #[test]
+    fn isolated_pixel_in_flat_region_is_not_antialiased() {
+        let mut img = RgbaImage::from_pixel(5, 5, *Rgba::from_slice(&[0, 0, 0, 255]));
+        img.put_pixel(2, 2, *Rgba::from_slice(&[255, 255, 255, 255]));
+
+        let antialias = AntialiasOptions::new();
+        let center = img.get_pixel(2, 2);
+        assert!(!is_antialiased(center, &img, &(2, 2), &antialias));
+    }
+
+    #[test]
+    fn antialiased_colored_transition_pixel_is_detected() {
+        // `green` and `gray` are different RGB triples with identical brightness,
+        // exercising the brightness-equality sibling check rather than RGB-exact
+        // equality, which real (non-grayscale) anti-aliased edges rarely satisfy.
+        let gray = *Rgba::from_slice(&[110, 110, 110, 255]);
+        let green = *Rgba::from_slice(&[35, 150, 100, 255]);
+        let black = *Rgba::from_slice(&[0, 0, 0, 255]);
+        let white = *Rgba::from_slice(&[255, 255, 255, 255]);
+
+        let mut img = RgbaImage::from_fn(5, 5, |x, _y| {
+            if x <= 1 {
+                black
+            } else if x == 2 {
+                gray
+            } else {
+                white
+            }
+        });
+        img.put_pixel(2, 2, green);
+
+        let antialias = AntialiasOptions::new();
+        let center = img.get_pixel(2, 2);
+        assert!(is_antialiased(center, &img, &(2, 2), &antialias));
+    }
+
+    #[test]
+    fn diff_image_uses_configured_diff_color() {
+        let img1 = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let img2 = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[255, 255, 255, 255]));
+
+        let opts = &ComparisonOptions::new()
+            .ignore_nothing()
+            .diff_color(10, 20, 30);
+
+        let r = compare_images(&img1, &img2, opts);
+        let out = r.image.to_rgba8();
+
+        assert_eq!(*out.get_pixel(0, 0), *Rgba::from_slice(&[10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn blend_factor_fades_unchanged_pixels_towards_white() {
+        let img1 = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[0, 0, 0, 255]));
+        let img2 = img1.clone();
+
+        let opts = &ComparisonOptions::new().blend_factor(0.5);
+        let r = compare_images(&img1, &img2, opts);
+        let out = r.image.to_rgba8();
+
+        assert_eq!(*out.get_pixel(0, 0), *Rgba::from_slice(&[127, 127, 127, 255]));
+    }
+
+    #[test]
+    fn find_subimage_locates_needle_in_haystack() {
+        let mut haystack = RgbaImage::from_pixel(6, 6, *Rgba::from_slice(&[0, 0, 0, 255]));
+
+        for y in 2..4 {
+            for x in 3..5 {
+                haystack.put_pixel(x, y, *Rgba::from_slice(&[200, 100, 50, 255]));
+            }
+        }
+
+        let needle = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[200, 100, 50, 255]));
+        let opts = &ComparisonOptions::new().ignore_nothing();
+
+        assert_eq!(find_subimage(&haystack, &needle, 0.0, opts), Some((3, 2)));
+        assert_eq!(find_all_subimages(&haystack, &needle, 0.0, opts), vec![(3, 2)]);
+
+        let missing = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[1, 2, 3, 255]));
+        assert_eq!(find_subimage(&haystack, &missing, 0.0, opts), None);
+    }
+
+    #[test]
+    fn find_subimage_returns_raster_order_first_match_with_multiple_hits() {
+        let mut haystack = RgbaImage::from_pixel(6, 6, *Rgba::from_slice(&[0, 0, 0, 255]));
+
+        // two identical 2x2 matches: one at (3, 2), one later at (0, 4).
+        for y in 2..4 {
+            for x in 3..5 {
+                haystack.put_pixel(x, y, *Rgba::from_slice(&[200, 100, 50, 255]));
+            }
+        }
+        for y in 4..6 {
+            for x in 0..2 {
+                haystack.put_pixel(x, y, *Rgba::from_slice(&[200, 100, 50, 255]));
+            }
+        }
+
+        let needle = RgbaImage::from_pixel(2, 2, *Rgba::from_slice(&[200, 100, 50, 255]));
+        let opts = &ComparisonOptions::new().ignore_nothing();
+
+        assert_eq!(find_subimage(&haystack, &needle, 0.0, opts), Some((3, 2)));
+        assert_eq!(
+            find_all_subimages(&haystack, &needle, 0.0, opts),
+            vec![(3, 2), (0, 4)]
+        );
+    }
+
+    #[test]
+    fn match_histogram_aligns_tone_mapped_images() {
+        let img1 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[200, 200, 200, 255]));
+        let img2 = RgbaImage::from_pixel(4, 4, *Rgba::from_slice(&[50, 50, 50, 255]));
+
+        let opts = &ComparisonOptions::new().ignore_nothing();
+        assert_eq!(get_mismatch_percent(&img1, &img2, opts), 100.0);
+
+        let opts = &ComparisonOptions::new().ignore_nothing().match_histogram();
+        assert_eq!(get_mismatch_percent(&img1, &img2, opts), 0.0);
+    }
 }
 
 trait RgbaEx {